@@ -1,11 +1,22 @@
 #![doc = include_str!("../readme.md")]
 
-use std::collections::vec_deque::VecDeque;
 use std::io::Stdout;
 use std::io::{stdout, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
 use termion::color;
 
+#[cfg(feature = "blocking")]
+use std::collections::vec_deque::VecDeque;
+
+#[cfg(not(feature = "blocking"))]
+use std::cell::UnsafeCell;
+#[cfg(not(feature = "blocking"))]
+use std::mem::MaybeUninit;
+#[cfg(not(feature = "blocking"))]
+use std::sync::atomic::AtomicUsize;
+
 /// Bound-buffer struct
 ///
 /// * `size` limits size of the circular queue
@@ -14,13 +25,21 @@ use termion::color;
 /// for queuing
 /// * `remove` Condvar and mutex'd bool paired together for inter-thread signalling buffer is ready
 /// for dequeuing
+/// * `watchers` extra condvar pairs (typically owned by a [`Selector`]) notified whenever
+/// `remove` is, so a consumer can block on several buffers at once
+/// * `closed` set by `close`, signals producers to stop queuing and consumers to drain and
+/// disconnect rather than block forever
+#[cfg(feature = "blocking")]
 pub struct BoundBuffer<T> {
     size: usize,
     buffer: Arc<Mutex<VecDeque<T>>>,
     add: Arc<(Mutex<bool>, Condvar)>,
     remove: Arc<(Mutex<bool>, Condvar)>,
+    watchers: Arc<Mutex<Vec<Arc<(Mutex<bool>, Condvar)>>>>,
+    closed: Arc<AtomicBool>,
 }
 
+#[cfg(feature = "blocking")]
 impl<T> BoundBuffer<T> {
     /// constructor for generic bound buffer
     pub fn new(size: usize) -> BoundBuffer<T> {
@@ -29,6 +48,57 @@ impl<T> BoundBuffer<T> {
             buffer: Arc::new(Mutex::new(VecDeque::<T>::with_capacity(size as usize))),
             add: Arc::new((Mutex::new(true), Condvar::new())),
             remove: Arc::new((Mutex::new(false), Condvar::new())),
+            watchers: Arc::new(Mutex::new(Vec::new())),
+            closed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Signals that no more values will be queued
+    ///
+    /// Producers calling [`queue`](Self::queue)/[`try_queue`](Self::try_queue) after `close`
+    /// fail instead of pushing. Consumers parked in [`dequeue`](Self::dequeue) drain whatever is
+    /// already buffered and then get `None` instead of blocking forever. `remove` and every
+    /// registered watcher are notified so parked consumers wake up to observe the flag.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+
+        let (lock_remove, cv_remove) = &*self.remove;
+        let mut ready_remove = lock_remove.lock().unwrap();
+        *ready_remove = true;
+        cv_remove.notify_all();
+        std::mem::drop(ready_remove);
+        self.notify_watchers();
+    }
+
+    /// Whether `close` has been called on this buffer (or a clone sharing its state)
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    /// Current number of buffered entries
+    pub fn len(&self) -> usize {
+        self.buffer.lock().unwrap().len()
+    }
+
+    /// Whether the buffer currently holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Registers an additional condvar pair to be notified alongside `remove`
+    ///
+    /// Used by [`Selector::add_dequeue`] so a single wait can be woken by any one of several
+    /// registered buffers becoming ready.
+    pub(crate) fn register_watcher(&self, pair: Arc<(Mutex<bool>, Condvar)>) {
+        self.watchers.lock().unwrap().push(pair);
+    }
+
+    /// Notifies every registered watcher condvar, mirroring the `remove` notification
+    fn notify_watchers(&self) {
+        for (lock, cv) in self.watchers.lock().unwrap().iter().map(|pair| &**pair) {
+            let mut ready = lock.lock().unwrap();
+            *ready = true;
+            cv.notify_one();
         }
     }
 
@@ -39,7 +109,13 @@ impl<T> BoundBuffer<T> {
     /// Any waiting dequeuing threads are signalled after push.
     /// `std::mem::drop( _mutex_ )` is used to explicitly unlock all mutex in inter-thread
     /// readiness checking. Changes are signalled by the condvar.
+    /// Once [`close`](Self::close) has been called, this becomes a no-op: the value is dropped
+    /// rather than pushed.
     pub fn queue(&self, val: T) -> () {
+        if self.is_closed() {
+            return;
+        }
+
         // check buffer readiness (has space), explicitly drop mutex guard
         let (lock_add, cv_add) = &*self.add;
         let mut ready_add = lock_add.lock().unwrap();
@@ -51,6 +127,9 @@ impl<T> BoundBuffer<T> {
 
         // thread wait until ready to add
         while !*ready_add {
+            if self.is_closed() {
+                return;
+            }
             ready_add = cv_add.wait(ready_add).unwrap();
             let buff = self.buffer.lock().unwrap();
             if buff.len() >= self.size {
@@ -71,6 +150,7 @@ impl<T> BoundBuffer<T> {
         *ready_remove = true;
         cv_remove.notify_one();
         std::mem::drop(ready_remove);
+        self.notify_watchers();
     }
 
     /// Function to perform thread-safe dequeue from bound-buffer.
@@ -80,7 +160,9 @@ impl<T> BoundBuffer<T> {
     /// Any waiting queuing threads are signalled after pop.
     /// `std::mem::drop( mutex )` is used to explicitly unlock all mutex in inter-thread
     /// readiness checking. Changes are signalled by the condvar.
-    pub fn dequeue(&self) -> T {
+    /// Once [`close`](Self::close) has been called, remaining buffered values are still
+    /// drained; once the buffer is empty this returns `None` instead of blocking forever.
+    pub fn dequeue(&self) -> Option<T> {
         // check buffer readiness (has entries), explicitly drop mutex guard
         let (lock_remove, cv_remove) = &*self.remove;
         let mut ready_remove = lock_remove.lock().unwrap();
@@ -90,21 +172,23 @@ impl<T> BoundBuffer<T> {
         }
         std::mem::drop(buff);
 
-        // thread wait until ready
+        // thread wait until ready, or until closed with nothing left to drain
         while !*ready_remove {
+            if self.is_closed() {
+                break;
+            }
             ready_remove = cv_remove.wait(ready_remove).unwrap();
             let buff = self.buffer.lock().unwrap();
-            if buff.is_empty() {
-                *ready_remove = false;
-            }
+            *ready_remove = !buff.is_empty();
             std::mem::drop(buff);
         }
         std::mem::drop(ready_remove);
 
-        // pop from buffer
+        // pop from buffer, if there is anything left to pop
         let mut buff = self.buffer.lock().unwrap();
-        let val: T = buff.pop_front().unwrap();
+        let val = buff.pop_front();
         std::mem::drop(buff);
+        let val = val?;
 
         // update state and notify
         let (lock_add, cv_add) = &*self.add;
@@ -121,10 +205,182 @@ impl<T> BoundBuffer<T> {
         }
         std::mem::drop(ready_remove);
 
-        return val;
+        Some(val)
+    }
+
+    /// Function to perform a non-blocking queue to bound-buffer
+    ///
+    /// Returns immediately instead of parking the thread: if the buffer is full, `val` is handed
+    /// back in `Err` so the caller can retry or drop it. On success the `remove` condvar is
+    /// notified exactly as in [`queue`](Self::queue). Fails the same way once
+    /// [`close`](Self::close) has been called.
+    pub fn try_queue(&self, val: T) -> Result<(), T> {
+        if self.is_closed() {
+            return Err(val);
+        }
+
+        let mut buff = self.buffer.lock().unwrap();
+        if buff.len() >= self.size {
+            return Err(val);
+        }
+        buff.push_back(val);
+        std::mem::drop(buff);
+
+        let (lock_remove, cv_remove) = &*self.remove;
+        let mut ready_remove = lock_remove.lock().unwrap();
+        *ready_remove = true;
+        cv_remove.notify_one();
+        std::mem::drop(ready_remove);
+        self.notify_watchers();
+
+        Ok(())
+    }
+
+    /// Function to perform a non-blocking dequeue from bound-buffer
+    ///
+    /// Returns immediately instead of parking the thread: `None` if the buffer is empty,
+    /// otherwise the popped value. On success the `add` condvar is notified exactly as in
+    /// [`dequeue`](Self::dequeue).
+    pub fn try_dequeue(&self) -> Option<T> {
+        let mut buff = self.buffer.lock().unwrap();
+        let val = buff.pop_front()?;
+        std::mem::drop(buff);
+
+        let (lock_add, cv_add) = &*self.add;
+        let mut ready_add = lock_add.lock().unwrap();
+        *ready_add = true;
+        cv_add.notify_one();
+        std::mem::drop(ready_add);
+
+        Some(val)
+    }
+
+    /// Function to perform a thread-safe queue to bound-buffer, bounded by a deadline
+    ///
+    /// Identical to [`queue`](Self::queue), but each wait on the `add` condvar is given the
+    /// remaining time until `timeout` has elapsed. The fullness predicate is rechecked after
+    /// every wake (spurious or not); if the deadline passes before the buffer has space, `val`
+    /// is handed back in `Err`. Also fails once [`close`](Self::close) has been called.
+    pub fn queue_timeout(&self, val: T, timeout: Duration) -> Result<(), T> {
+        if self.is_closed() {
+            return Err(val);
+        }
+
+        let deadline = Instant::now() + timeout;
+
+        let (lock_add, cv_add) = &*self.add;
+        let mut ready_add = lock_add.lock().unwrap();
+        let buff = self.buffer.lock().unwrap();
+        if buff.len() >= self.size {
+            *ready_add = false;
+        }
+        std::mem::drop(buff);
+
+        while !*ready_add {
+            if self.is_closed() {
+                return Err(val);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(val);
+            }
+            let (guard, timeout_result) = cv_add.wait_timeout(ready_add, deadline - now).unwrap();
+            ready_add = guard;
+            if timeout_result.timed_out() && !*ready_add {
+                let buff = self.buffer.lock().unwrap();
+                if buff.len() < self.size {
+                    *ready_add = true;
+                }
+                std::mem::drop(buff);
+                if !*ready_add {
+                    return Err(val);
+                }
+                continue;
+            }
+            let buff = self.buffer.lock().unwrap();
+            if buff.len() >= self.size {
+                *ready_add = false;
+            }
+            std::mem::drop(buff);
+        }
+        std::mem::drop(ready_add);
+
+        let mut buff = self.buffer.lock().unwrap();
+        buff.push_back(val);
+        std::mem::drop(buff);
+
+        let (lock_remove, cv_remove) = &*self.remove;
+        let mut ready_remove = lock_remove.lock().unwrap();
+        *ready_remove = true;
+        cv_remove.notify_one();
+        std::mem::drop(ready_remove);
+        self.notify_watchers();
+
+        Ok(())
+    }
+
+    /// Function to perform a thread-safe dequeue from bound-buffer, bounded by a deadline
+    ///
+    /// Identical to [`dequeue`](Self::dequeue), but each wait on the `remove` condvar is given
+    /// the remaining time until `timeout` has elapsed. The emptiness predicate is rechecked
+    /// after every wake (spurious or not); if the deadline passes before a value is available,
+    /// `None` is returned instead of blocking forever.
+    pub fn dequeue_timeout(&self, timeout: Duration) -> Option<T> {
+        let deadline = Instant::now() + timeout;
+
+        let (lock_remove, cv_remove) = &*self.remove;
+        let mut ready_remove = lock_remove.lock().unwrap();
+        let buff = self.buffer.lock().unwrap();
+        if buff.is_empty() {
+            *ready_remove = false;
+        }
+        std::mem::drop(buff);
+
+        while !*ready_remove {
+            if self.is_closed() {
+                return None;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            let (guard, timeout_result) =
+                cv_remove.wait_timeout(ready_remove, deadline - now).unwrap();
+            ready_remove = guard;
+            if timeout_result.timed_out() && !*ready_remove {
+                let buff = self.buffer.lock().unwrap();
+                if !buff.is_empty() {
+                    *ready_remove = true;
+                }
+                std::mem::drop(buff);
+                if !*ready_remove {
+                    return None;
+                }
+                continue;
+            }
+            let buff = self.buffer.lock().unwrap();
+            if buff.is_empty() {
+                *ready_remove = false;
+            }
+            std::mem::drop(buff);
+        }
+        std::mem::drop(ready_remove);
+
+        let mut buff = self.buffer.lock().unwrap();
+        let val: T = buff.pop_front().unwrap();
+        std::mem::drop(buff);
+
+        let (lock_add, cv_add) = &*self.add;
+        let mut ready_add = lock_add.lock().unwrap();
+        *ready_add = true;
+        cv_add.notify_one();
+        std::mem::drop(ready_add);
+
+        Some(val)
     }
 }
 
+#[cfg(feature = "blocking")]
 impl<T> Clone for BoundBuffer<T> {
     fn clone(&self) -> BoundBuffer<T> {
         BoundBuffer::<T> {
@@ -132,6 +388,455 @@ impl<T> Clone for BoundBuffer<T> {
             buffer: self.buffer.clone(),
             add: self.add.clone(),
             remove: self.remove.clone(),
+            watchers: self.watchers.clone(),
+            closed: self.closed.clone(),
+        }
+    }
+}
+
+/// Blocks a single consumer on the first ready buffer out of several registered [`BoundBuffer`]s
+///
+/// Each registered buffer has the selector's shared condvar pair added to its `watchers`, so a
+/// `queue` on any one of them wakes the selector. On wake, all registered buffers are rescanned
+/// for a non-empty one, guarding against spurious wakeups and against races where another thread
+/// drained the buffer first. Works against either `BoundBuffer` backend: the lock-free variant
+/// has no condvars of its own, but it still maintains a `watchers` list purely to feed this type.
+pub struct Selector<T> {
+    buffers: Vec<BoundBuffer<T>>,
+    ready: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl<T> Selector<T> {
+    /// constructor for an empty selector
+    pub fn new() -> Selector<T> {
+        Selector {
+            buffers: Vec::new(),
+            ready: Arc::new((Mutex::new(false), Condvar::new())),
+        }
+    }
+
+    /// Registers a buffer to be considered by `select`/`try_select`/`select_timeout`
+    ///
+    /// Clones `buffer` (the clone shares the same underlying queue) and wires the selector's
+    /// condvar pair into its `watchers` so a `queue` on it will wake this selector.
+    pub fn add_dequeue(&mut self, buffer: &BoundBuffer<T>) {
+        buffer.register_watcher(self.ready.clone());
+        self.buffers.push(buffer.clone());
+    }
+
+    /// Returns the index of the first registered buffer found non-empty, if any
+    fn ready_index(&self) -> Option<usize> {
+        self.buffers.iter().position(|buffer| !buffer.is_empty())
+    }
+
+    /// Blocks until at least one registered buffer is non-empty, returning its index
+    ///
+    /// Rescans all registered buffers on every wake since the ready buffer is not identified by
+    /// the wakeup itself, only that some buffer somewhere became ready. The `ready` flag is
+    /// rechecked under the lock before waiting (and reset after), so a `notify` racing ahead of
+    /// this call is not lost: we'd otherwise wait forever on a condvar nobody signals again even
+    /// though a buffer already has data.
+    pub fn select(&self) -> usize {
+        loop {
+            if let Some(idx) = self.ready_index() {
+                return idx;
+            }
+
+            let (lock, cv) = &*self.ready;
+            let mut ready = lock.lock().unwrap();
+            while !*ready {
+                ready = cv.wait(ready).unwrap();
+            }
+            *ready = false;
+        }
+    }
+
+    /// Non-blocking companion to `select`: returns `None` if no buffer is currently ready
+    pub fn try_select(&self) -> Option<usize> {
+        self.ready_index()
+    }
+
+    /// `select`, bounded by a deadline; returns `None` if it passes before a buffer is ready
+    pub fn select_timeout(&self, timeout: Duration) -> Option<usize> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(idx) = self.ready_index() {
+                return Some(idx);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+
+            let (lock, cv) = &*self.ready;
+            let mut ready = lock.lock().unwrap();
+            while !*ready {
+                let now = Instant::now();
+                if now >= deadline {
+                    return None;
+                }
+                let (guard, timeout_result) = cv.wait_timeout(ready, deadline - now).unwrap();
+                ready = guard;
+                if timeout_result.timed_out() && !*ready {
+                    return None;
+                }
+            }
+            *ready = false;
+        }
+    }
+}
+
+impl<T> Default for Selector<T> {
+    fn default() -> Selector<T> {
+        Selector::new()
+    }
+}
+
+/// A single slot in the lock-free ring buffer.
+///
+/// Each cell carries its own `sequence` so a producer or consumer can tell, without a lock,
+/// whether the slot is the one it is looking for: ready to write into (enqueue) or ready to
+/// read from (dequeue). This is the cell layout from Dmitry Vyukov's bounded MPMC queue.
+#[cfg(not(feature = "blocking"))]
+struct Cell<T> {
+    sequence: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// Bound-buffer struct (lock-free variant)
+///
+/// * `size` is `new`'s requested size rounded up to the next power of two
+/// * `mask` is `size - 1`, used to turn a monotonic position into a cell index without `%`
+/// * `buffer` the fixed array of [`Cell`]s making up the ring
+/// * `enqueue_pos`/`dequeue_pos` monotonically increasing positions CAS'd by producers/consumers
+/// * `watchers` condvar pairs (typically owned by a [`Selector`]) notified on every successful
+/// `queue`/`try_queue`/`queue_timeout`, so a consumer can block on several buffers at once despite
+/// this backend having no condvar of its own
+/// * `closed` set by `close`, signals producers to stop queuing and consumers to drain and
+/// disconnect rather than spin forever
+///
+/// Claiming a cell takes no lock; a full or empty buffer just spins until a cell's sequence
+/// number shows it is ready. A successful queue does briefly lock `watchers` to notify them. Enable the `blocking` feature to get the
+/// Mutex+Condvar implementation instead, which parks threads rather than spinning.
+#[cfg(not(feature = "blocking"))]
+pub struct BoundBuffer<T> {
+    size: usize,
+    mask: usize,
+    buffer: Arc<Vec<Cell<T>>>,
+    enqueue_pos: Arc<AtomicUsize>,
+    dequeue_pos: Arc<AtomicUsize>,
+    watchers: Arc<Mutex<Vec<Arc<(Mutex<bool>, Condvar)>>>>,
+    closed: Arc<AtomicBool>,
+}
+
+#[cfg(not(feature = "blocking"))]
+unsafe impl<T: Send> Send for BoundBuffer<T> {}
+#[cfg(not(feature = "blocking"))]
+unsafe impl<T: Send> Sync for BoundBuffer<T> {}
+
+#[cfg(not(feature = "blocking"))]
+impl<T> BoundBuffer<T> {
+    /// constructor for generic bound buffer
+    ///
+    /// `size` is rounded up to the next power of two so that a cell index can be computed with
+    /// a mask (`pos & mask`) instead of a modulo.
+    pub fn new(size: usize) -> BoundBuffer<T> {
+        let size = size.next_power_of_two();
+        let buffer: Vec<Cell<T>> = (0..size)
+            .map(|i| Cell {
+                sequence: AtomicUsize::new(i),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect();
+
+        BoundBuffer::<T> {
+            size,
+            mask: size - 1,
+            buffer: Arc::new(buffer),
+            enqueue_pos: Arc::new(AtomicUsize::new(0)),
+            dequeue_pos: Arc::new(AtomicUsize::new(0)),
+            watchers: Arc::new(Mutex::new(Vec::new())),
+            closed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Signals that no more values will be queued
+    ///
+    /// Producers calling [`queue`](Self::queue)/[`try_queue`](Self::try_queue)/
+    /// [`queue_timeout`](Self::queue_timeout) after `close` fail instead of pushing. Consumers
+    /// spinning in [`dequeue`](Self::dequeue) drain whatever is already buffered and then get
+    /// `None` instead of spinning forever. Registered watchers are notified so a parked
+    /// [`Selector`] wakes up to observe the flag.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.notify_watchers();
+    }
+
+    /// Whether `close` has been called on this buffer (or a clone sharing its state)
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    /// Current number of buffered entries
+    ///
+    /// A snapshot: `enqueue_pos` and `dequeue_pos` are loaded separately, so under concurrent
+    /// access the true count may have already moved by the time this returns. Good enough for
+    /// [`Selector`]'s readiness scan, which always rechecks before acting on it.
+    pub fn len(&self) -> usize {
+        self.enqueue_pos.load(Ordering::Relaxed) - self.dequeue_pos.load(Ordering::Relaxed)
+    }
+
+    /// Whether the buffer currently holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Registers an additional condvar pair to be notified on every successful queue
+    ///
+    /// Used by [`Selector::add_dequeue`] so a single wait can be woken by any one of several
+    /// registered buffers becoming ready.
+    pub(crate) fn register_watcher(&self, pair: Arc<(Mutex<bool>, Condvar)>) {
+        self.watchers.lock().unwrap().push(pair);
+    }
+
+    /// Notifies every registered watcher condvar
+    fn notify_watchers(&self) {
+        for (lock, cv) in self.watchers.lock().unwrap().iter().map(|pair| &**pair) {
+            let mut ready = lock.lock().unwrap();
+            *ready = true;
+            cv.notify_one();
+        }
+    }
+
+    /// Function to perform thread-safe queue to bound-buffer
+    ///
+    /// Loads the current enqueue position and inspects the cell it points to. If the cell's
+    /// sequence equals the position the cell is free, and a `compare_exchange_weak` on
+    /// `enqueue_pos` is attempted to claim it; on success the value is written and the cell's
+    /// sequence is bumped so a consumer can find it, and any registered watchers are notified.
+    /// A negative sequence difference means the buffer is full and the thread spins until a
+    /// dequeue frees a cell; a positive difference means another producer raced ahead, so the
+    /// position is reloaded and retried.
+    /// Once [`close`](Self::close) has been called, this becomes a no-op: the value is dropped
+    /// rather than pushed.
+    pub fn queue(&self, val: T) {
+        if self.is_closed() {
+            return;
+        }
+
+        let mut pos = self.enqueue_pos.load(Ordering::Relaxed);
+        loop {
+            let cell = &self.buffer[pos & self.mask];
+            let seq = cell.sequence.load(Ordering::Acquire);
+            let diff = seq as isize - pos as isize;
+
+            if diff == 0 {
+                if self
+                    .enqueue_pos
+                    .compare_exchange_weak(pos, pos + 1, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    unsafe { (*cell.value.get()).write(val) };
+                    cell.sequence.store(pos + 1, Ordering::Release);
+                    self.notify_watchers();
+                    return;
+                }
+                std::hint::spin_loop();
+            } else if diff < 0 {
+                // buffer full, spin until a dequeue frees a cell or the buffer is closed
+                if self.is_closed() {
+                    return;
+                }
+                std::hint::spin_loop();
+            }
+            pos = self.enqueue_pos.load(Ordering::Relaxed);
+        }
+    }
+
+    /// Function to perform thread-safe dequeue from bound-buffer.
+    ///
+    /// Symmetric to [`queue`](Self::queue): loads the current dequeue position and inspects the
+    /// cell it points to. The cell is ready to read once its sequence is one past the position;
+    /// a successful `compare_exchange_weak` on `dequeue_pos` claims it, the value is read out and
+    /// the cell's sequence is set to `pos + size` so the slot becomes available for the producer
+    /// that wraps around to it next. An empty buffer is spun on until a queue produces.
+    /// Once [`close`](Self::close) has been called, remaining buffered values are still drained;
+    /// once the buffer is empty this returns `None` instead of spinning forever.
+    pub fn dequeue(&self) -> Option<T> {
+        let mut pos = self.dequeue_pos.load(Ordering::Relaxed);
+        loop {
+            let cell = &self.buffer[pos & self.mask];
+            let seq = cell.sequence.load(Ordering::Acquire);
+            let diff = seq as isize - (pos as isize + 1);
+
+            if diff == 0 {
+                if self
+                    .dequeue_pos
+                    .compare_exchange_weak(pos, pos + 1, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    let val = unsafe { (*cell.value.get()).assume_init_read() };
+                    cell.sequence.store(pos + self.size, Ordering::Release);
+                    return Some(val);
+                }
+                std::hint::spin_loop();
+            } else if diff < 0 {
+                // buffer empty, spin until a queue produces or the buffer is closed
+                if self.is_closed() {
+                    return None;
+                }
+                std::hint::spin_loop();
+            }
+            pos = self.dequeue_pos.load(Ordering::Relaxed);
+        }
+    }
+
+    /// Function to perform a non-blocking queue to bound-buffer
+    ///
+    /// Single CAS attempt instead of spinning: if the cell is not free (buffer full) or another
+    /// producer wins the race to claim it, `val` is handed back in `Err` instead of retrying.
+    /// Fails the same way once [`close`](Self::close) has been called.
+    ///
+    /// Uses `compare_exchange` rather than `compare_exchange_weak`: a one-shot call can't absorb
+    /// a spurious failure by looping back around, so a weak CAS would misreport a free/ready
+    /// slot as full/empty under contention on platforms with LL/SC.
+    pub fn try_queue(&self, val: T) -> Result<(), T> {
+        if self.is_closed() {
+            return Err(val);
+        }
+
+        let pos = self.enqueue_pos.load(Ordering::Relaxed);
+        let cell = &self.buffer[pos & self.mask];
+        let seq = cell.sequence.load(Ordering::Acquire);
+        if seq as isize - pos as isize != 0 {
+            return Err(val);
+        }
+        if self
+            .enqueue_pos
+            .compare_exchange(pos, pos + 1, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            return Err(val);
+        }
+        unsafe { (*cell.value.get()).write(val) };
+        cell.sequence.store(pos + 1, Ordering::Release);
+        self.notify_watchers();
+        Ok(())
+    }
+
+    /// Function to perform a non-blocking dequeue from bound-buffer
+    ///
+    /// Single CAS attempt instead of spinning: `None` if the cell is not yet readable (buffer
+    /// empty) or another consumer wins the race to claim it.
+    ///
+    /// Uses `compare_exchange` rather than `compare_exchange_weak`: a one-shot call can't absorb
+    /// a spurious failure by looping back around, so a weak CAS would misreport a free/ready
+    /// slot as full/empty under contention on platforms with LL/SC.
+    pub fn try_dequeue(&self) -> Option<T> {
+        let pos = self.dequeue_pos.load(Ordering::Relaxed);
+        let cell = &self.buffer[pos & self.mask];
+        let seq = cell.sequence.load(Ordering::Acquire);
+        if seq as isize - (pos as isize + 1) != 0 {
+            return None;
+        }
+        if self
+            .dequeue_pos
+            .compare_exchange(pos, pos + 1, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            return None;
+        }
+        let val = unsafe { (*cell.value.get()).assume_init_read() };
+        cell.sequence.store(pos + self.size, Ordering::Release);
+        Some(val)
+    }
+
+    /// Function to perform a thread-safe queue to bound-buffer, bounded by a deadline
+    ///
+    /// Identical to [`queue`](Self::queue), but the full-buffer spin is bounded: the deadline is
+    /// rechecked every time the claimed cell is not yet free, and `val` is handed back in `Err`
+    /// once it passes. Also fails once [`close`](Self::close) has been called.
+    pub fn queue_timeout(&self, val: T, timeout: Duration) -> Result<(), T> {
+        if self.is_closed() {
+            return Err(val);
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut pos = self.enqueue_pos.load(Ordering::Relaxed);
+        loop {
+            let cell = &self.buffer[pos & self.mask];
+            let seq = cell.sequence.load(Ordering::Acquire);
+            let diff = seq as isize - pos as isize;
+
+            if diff == 0 {
+                if self
+                    .enqueue_pos
+                    .compare_exchange_weak(pos, pos + 1, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    unsafe { (*cell.value.get()).write(val) };
+                    cell.sequence.store(pos + 1, Ordering::Release);
+                    self.notify_watchers();
+                    return Ok(());
+                }
+                std::hint::spin_loop();
+            } else if diff < 0 {
+                if self.is_closed() || Instant::now() >= deadline {
+                    return Err(val);
+                }
+                std::hint::spin_loop();
+            }
+            pos = self.enqueue_pos.load(Ordering::Relaxed);
+        }
+    }
+
+    /// Function to perform a thread-safe dequeue from bound-buffer, bounded by a deadline
+    ///
+    /// Identical to [`dequeue`](Self::dequeue), but the empty-buffer spin is bounded: the
+    /// deadline is rechecked every time the claimed cell is not yet readable, and `None` is
+    /// returned once it passes.
+    pub fn dequeue_timeout(&self, timeout: Duration) -> Option<T> {
+        let deadline = Instant::now() + timeout;
+        let mut pos = self.dequeue_pos.load(Ordering::Relaxed);
+        loop {
+            let cell = &self.buffer[pos & self.mask];
+            let seq = cell.sequence.load(Ordering::Acquire);
+            let diff = seq as isize - (pos as isize + 1);
+
+            if diff == 0 {
+                if self
+                    .dequeue_pos
+                    .compare_exchange_weak(pos, pos + 1, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    let val = unsafe { (*cell.value.get()).assume_init_read() };
+                    cell.sequence.store(pos + self.size, Ordering::Release);
+                    return Some(val);
+                }
+                std::hint::spin_loop();
+            } else if diff < 0 {
+                if self.is_closed() || Instant::now() >= deadline {
+                    return None;
+                }
+                std::hint::spin_loop();
+            }
+            pos = self.dequeue_pos.load(Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(not(feature = "blocking"))]
+impl<T> Clone for BoundBuffer<T> {
+    fn clone(&self) -> BoundBuffer<T> {
+        BoundBuffer::<T> {
+            size: self.size,
+            mask: self.mask,
+            buffer: self.buffer.clone(),
+            enqueue_pos: self.enqueue_pos.clone(),
+            dequeue_pos: self.dequeue_pos.clone(),
+            watchers: self.watchers.clone(),
+            closed: self.closed.clone(),
         }
     }
 }
@@ -140,7 +845,18 @@ impl<T> Clone for BoundBuffer<T> {
 ///
 /// Typical histogram definition. Configurable with upper and lower bounds, number of bins and so
 /// on. `std::vec` used to store counts of binned samples. Various associated information for the
-/// padding and the maximum value on the y-axis. No statistical tools included (yet).
+/// padding and the maximum value on the y-axis. `mean`/`variance`/`std_dev`/`mode` give basic
+/// statistics over the filled samples.
+///
+/// Bins are uniform width by default (see [`Histogram::new`]), but [`Histogram::with_buckets`]
+/// builds a histogram from arbitrary ascending bucket boundaries instead, for latency-style
+/// distributions that need fine resolution near the low end and coarse resolution in the tail.
+/// `boundaries` is empty for a uniform-bin histogram.
+///
+/// With the `serde` feature enabled this derives `Serialize`/`Deserialize`, and [`merge`](
+/// Histogram::merge) folds together two structurally compatible histograms, so several threads
+/// or processes can each accumulate independently and combine their results for a final `draw`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Histogram {
     bins: usize,
     lower: f32,
@@ -151,6 +867,11 @@ pub struct Histogram {
     height: usize,
     width: usize,
     x_pad: usize,
+    boundaries: Vec<f32>,
+    sum: f32,
+    sum_sq: f32,
+    underflow: usize,
+    overflow: usize,
 }
 
 impl Histogram {
@@ -179,6 +900,180 @@ impl Histogram {
             height: (bins / 2) as usize,
             x_pad: 10,
             //y_pad: 0
+            boundaries: Vec::new(),
+            sum: 0f32,
+            sum_sq: 0f32,
+            underflow: 0usize,
+            overflow: 0usize,
+        }
+    }
+
+    /// Histogram constructor using explicit, ascending bucket upper bounds
+    ///
+    /// Unlike [`Histogram::new`], bins need not be uniform width: `boundaries` gives the upper
+    /// bound of each bucket in ascending order. An implicit `+Inf` overflow bucket is appended
+    /// so values above the largest boundary are still counted. Bucket counts are cumulative in
+    /// the sense of [`Histogram::cumulative_counts`]: bucket `i`'s cumulative count covers every
+    /// observation less than or equal to `boundaries[i]`.
+    /// # Panics
+    /// Will panic if `boundaries` is empty or not strictly ascending.
+    pub fn with_buckets(boundaries: Vec<f32>) -> Histogram {
+        if boundaries.is_empty() {
+            panic!("Bucket boundaries cannot be empty");
+        }
+        if boundaries.windows(2).any(|w| w[0] >= w[1]) {
+            panic!("Bucket boundaries must be strictly ascending");
+        }
+
+        let bins = boundaries.len() + 1; // + 1 for the implicit +Inf overflow bucket
+        Histogram {
+            bins,
+            lower: boundaries[0],
+            upper: *boundaries.last().unwrap(),
+            max: 0f32,
+            counts: vec![0u32; bins],
+            entries: 0usize,
+            width: bins,
+            height: (bins / 2).max(1),
+            x_pad: 10,
+            boundaries,
+            sum: 0f32,
+            sum_sq: 0f32,
+            underflow: 0usize,
+            overflow: 0usize,
+        }
+    }
+
+    /// Returns the cumulative count for each bucket
+    ///
+    /// Bucket `i` is the count of every observation less than or equal to its upper bound
+    /// (`boundaries[i]`), or every observation at all for the final `+Inf` overflow bucket. Only
+    /// meaningful for histograms built with [`Histogram::with_buckets`].
+    pub fn cumulative_counts(&self) -> Vec<u32> {
+        let mut running = 0u32;
+        self.counts
+            .iter()
+            .map(|&count| {
+                running += count;
+                running
+            })
+            .collect()
+    }
+
+    /// Estimates the value at quantile `q` (in `[0, 1]`)
+    ///
+    /// Finds the bucket where the cumulative count first reaches `q * entries` and linearly
+    /// interpolates within it between that bucket's lower and upper bounds. Only meaningful for
+    /// histograms built with [`Histogram::with_buckets`]; returns `0.0` if there are no bucket
+    /// boundaries or no entries.
+    pub fn quantile(&self, q: f32) -> f32 {
+        if self.boundaries.is_empty() || self.entries == 0 {
+            return 0f32;
+        }
+
+        let target = q * self.entries as f32;
+        let cumulative = self.cumulative_counts();
+        let idx = match cumulative.iter().position(|&count| (count as f32) >= target) {
+            Some(idx) => idx,
+            None => return self.upper,
+        };
+
+        // the trailing +Inf bucket has no upper bound to interpolate against
+        let bucket_lower = if idx == 0 { 0f32 } else { self.boundaries[idx - 1] };
+        if idx == self.boundaries.len() {
+            return bucket_lower;
+        }
+
+        let bucket_upper = self.boundaries[idx];
+        let prev_cumulative = if idx == 0 { 0f32 } else { cumulative[idx - 1] as f32 };
+        let count_in_bucket = cumulative[idx] as f32 - prev_cumulative;
+        if count_in_bucket == 0f32 {
+            return bucket_upper;
+        }
+
+        let fraction = (target - prev_cumulative) / count_in_bucket;
+        bucket_lower + fraction * (bucket_upper - bucket_lower)
+    }
+
+    /// Total number of values passed to [`fill`](Self::fill), including underflow/overflow
+    pub fn entries(&self) -> usize {
+        self.entries
+    }
+
+    /// Counts of values that fell outside `[lower, upper]`, as `(underflow, overflow)`
+    ///
+    /// For a histogram built with [`Histogram::with_buckets`] there is no underflow concept
+    /// (every value below the first boundary still lands in bucket 0), so `underflow` is always
+    /// `0` and `overflow` is the count in the trailing `+Inf` bucket.
+    pub fn underflow_overflow(&self) -> (usize, usize) {
+        (self.underflow, self.overflow)
+    }
+
+    /// Arithmetic mean of all observed (in-range) values
+    ///
+    /// Computed from the exact running sum maintained in [`fill`](Self::fill) rather than
+    /// approximated from bin midpoints.
+    pub fn mean(&self) -> f32 {
+        // uniform histograms leave discarded underflow/overflow values out of `sum`
+        let n = if self.boundaries.is_empty() {
+            self.entries - self.underflow - self.overflow
+        } else {
+            self.entries
+        };
+        if n == 0 {
+            return 0f32;
+        }
+        self.sum / n as f32
+    }
+
+    /// Population variance of all observed (in-range) values
+    ///
+    /// Computed from the exact running sum and sum-of-squares maintained in
+    /// [`fill`](Self::fill): `E[x^2] - E[x]^2`.
+    pub fn variance(&self) -> f32 {
+        let n = if self.boundaries.is_empty() {
+            self.entries - self.underflow - self.overflow
+        } else {
+            self.entries
+        };
+        if n == 0 {
+            return 0f32;
+        }
+        let n = n as f32;
+        (self.sum_sq / n) - (self.sum / n).powi(2)
+    }
+
+    /// Standard deviation of all observed (in-range) values, i.e. `sqrt(variance())`
+    pub fn std_dev(&self) -> f32 {
+        f32::sqrt(self.variance())
+    }
+
+    /// Returns the center of the most-populated bin
+    ///
+    /// Unlike `mean`/`variance` this has no exact running counterpart and is always computed
+    /// from `counts`: the midpoint of a uniform bin, or the midpoint of a bucket's lower and
+    /// upper bounds for a histogram built with [`Histogram::with_buckets`] (the trailing `+Inf`
+    /// bucket is excluded since it has no finite upper bound to take a midpoint of).
+    pub fn mode(&self) -> f32 {
+        if !self.boundaries.is_empty() {
+            return match self.counts[..self.boundaries.len()]
+                .iter()
+                .enumerate()
+                .max_by_key(|&(_, &count)| count)
+            {
+                Some((idx, _)) => {
+                    let lower = if idx == 0 { 0f32 } else { self.boundaries[idx - 1] };
+                    let upper = self.boundaries[idx];
+                    (lower + upper) / 2f32
+                }
+                None => 0f32,
+            };
+        }
+
+        let bin_width = (self.upper - self.lower) / (self.bins as f32);
+        match self.counts.iter().enumerate().max_by_key(|&(_, &count)| count) {
+            Some((idx, _)) => self.lower + (idx as f32 + 0.5) * bin_width,
+            None => 0f32,
         }
     }
 
@@ -248,10 +1143,39 @@ impl Histogram {
     /// Histogram bins are indexed from 1. Underflow and overflow entries will be returned as 0.
     /// Takes a value `val` to be binned into the histogram, increments the count of the respective
     /// bin and returns its index.
+    ///
+    /// For a histogram built with [`Histogram::with_buckets`], `val` falls into the first bucket
+    /// whose boundary is greater than or equal to it, or the trailing `+Inf` bucket otherwise;
+    /// unlike the uniform-bin case this is always recorded (there is no overflow/underflow
+    /// discard). Either way, the running `sum` and sum-of-squares of observed values are updated
+    /// so [`mean`](Self::mean)/[`variance`](Self::variance) can be computed exactly rather than
+    /// approximated from bin midpoints.
     pub fn fill(&mut self, val: f32) -> usize {
+        if !self.boundaries.is_empty() {
+            let bin = self
+                .boundaries
+                .iter()
+                .position(|&boundary| val <= boundary)
+                .unwrap_or(self.boundaries.len());
+
+            self.counts[bin] += 1;
+            self.entries += 1;
+            self.sum += val;
+            self.sum_sq += val * val;
+            if bin == self.boundaries.len() {
+                self.overflow += 1;
+            }
+            return bin + 1;
+        }
+
         // catch any overflowing values
         if val < self.lower || val > self.upper {
             self.entries += 1;
+            if val < self.lower {
+                self.underflow += 1;
+            } else {
+                self.overflow += 1;
+            }
             return 0usize;
         }
 
@@ -262,6 +1186,8 @@ impl Histogram {
         // increment bin and entry count, return histogram bin.
         self.counts[bin] += 1;
         self.entries += 1;
+        self.sum += val;
+        self.sum_sq += val * val;
         bin + 1
     }
 
@@ -354,9 +1280,70 @@ impl Histogram {
         // flush stdout buffer and finish
         stdout.flush().unwrap();
     }
+
+    /// Folds another structurally compatible histogram into `self`
+    ///
+    /// Adds `counts`, `entries`, `sum`, `sum_sq`, `underflow` and `overflow` element-wise, so
+    /// several independently accumulated histograms (one per producer thread or process, say)
+    /// can be combined into a single distribution before drawing. The two histograms must share
+    /// the same binning (same bin count and, for uniform histograms, the same `lower`/`upper`,
+    /// or for bucketed histograms the same `boundaries`); otherwise `self` is left unchanged and
+    /// a [`MergeError`] is returned.
+    pub fn merge(&mut self, other: &Histogram) -> Result<(), MergeError> {
+        if self.counts.len() != other.counts.len() {
+            return Err(MergeError::BinCountMismatch {
+                expected: self.counts.len(),
+                found: other.counts.len(),
+            });
+        }
+        if self.lower != other.lower || self.upper != other.upper || self.boundaries != other.boundaries {
+            return Err(MergeError::BoundsMismatch);
+        }
+
+        for (count, other_count) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count += other_count;
+        }
+        self.entries += other.entries;
+        self.sum += other.sum;
+        self.sum_sq += other.sum_sq;
+        self.underflow += other.underflow;
+        self.overflow += other.overflow;
+
+        Ok(())
+    }
 }
 
-#[cfg(test)]
+/// Error returned by [`Histogram::merge`] when two histograms have incompatible binning
+#[derive(Debug, PartialEq)]
+pub enum MergeError {
+    /// The two histograms have a different number of bins
+    BinCountMismatch {
+        /// Number of bins in `self`
+        expected: usize,
+        /// Number of bins in the histogram passed to `merge`
+        found: usize,
+    },
+    /// The two histograms have the same bin count but different bounds or bucket boundaries
+    BoundsMismatch,
+}
+
+impl std::fmt::Display for MergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeError::BinCountMismatch { expected, found } => write!(
+                f,
+                "histogram bin count mismatch: expected {expected}, found {found}"
+            ),
+            MergeError::BoundsMismatch => {
+                write!(f, "histogram bounds or bucket boundaries differ")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+#[cfg(all(test, feature = "blocking"))]
 mod tests {
     use super::*;
 
@@ -403,7 +1390,7 @@ mod tests {
     fn test_remove() {
         let bb: BoundBuffer<f32> = BoundBuffer::new(30);
         bb.queue(3f32);
-        let val = bb.dequeue();
+        let val = bb.dequeue().unwrap();
 
         assert_eq!(bb.size, 30);
         assert_eq!(val, 3f32);
@@ -421,4 +1408,339 @@ mod tests {
         println!("{}", *removemutex);
         assert!(!*removemutex);
     }
+
+    #[test]
+    fn test_try_queue_full() {
+        let bb: BoundBuffer<f32> = BoundBuffer::new(1);
+        assert_eq!(bb.try_queue(1f32), Ok(()));
+        assert_eq!(bb.try_queue(2f32), Err(2f32));
+    }
+
+    #[test]
+    fn test_try_dequeue_empty() {
+        let bb: BoundBuffer<f32> = BoundBuffer::new(1);
+        assert_eq!(bb.try_dequeue(), None);
+        bb.queue(3f32);
+        assert_eq!(bb.try_dequeue(), Some(3f32));
+        assert_eq!(bb.try_dequeue(), None);
+    }
+
+    #[test]
+    fn test_dequeue_timeout_elapses() {
+        let bb: BoundBuffer<f32> = BoundBuffer::new(1);
+        let start = std::time::Instant::now();
+        assert_eq!(bb.dequeue_timeout(std::time::Duration::from_millis(20)), None);
+        assert!(start.elapsed() >= std::time::Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_queue_timeout_elapses() {
+        let bb: BoundBuffer<f32> = BoundBuffer::new(1);
+        bb.queue(1f32);
+        assert_eq!(
+            bb.queue_timeout(2f32, std::time::Duration::from_millis(20)),
+            Err(2f32)
+        );
+    }
+
+    #[test]
+    fn test_queue_dequeue_timeout_succeed() {
+        let bb: BoundBuffer<f32> = BoundBuffer::new(1);
+        assert_eq!(bb.queue_timeout(3f32, std::time::Duration::from_millis(20)), Ok(()));
+        assert_eq!(
+            bb.dequeue_timeout(std::time::Duration::from_millis(20)),
+            Some(3f32)
+        );
+    }
+
+    #[test]
+    fn test_selector_try_select_none_ready() {
+        let a: BoundBuffer<f32> = BoundBuffer::new(10);
+        let b: BoundBuffer<f32> = BoundBuffer::new(10);
+        let mut selector = Selector::new();
+        selector.add_dequeue(&a);
+        selector.add_dequeue(&b);
+
+        assert_eq!(selector.try_select(), None);
+    }
+
+    #[test]
+    fn test_selector_select_picks_ready_buffer() {
+        let a: BoundBuffer<f32> = BoundBuffer::new(10);
+        let b: BoundBuffer<f32> = BoundBuffer::new(10);
+        let mut selector = Selector::new();
+        selector.add_dequeue(&a);
+        selector.add_dequeue(&b);
+
+        b.queue(1f32);
+
+        assert_eq!(selector.select(), 1);
+    }
+
+    #[test]
+    fn test_selector_select_timeout_elapses() {
+        let a: BoundBuffer<f32> = BoundBuffer::new(10);
+        let mut selector = Selector::new();
+        selector.add_dequeue(&a);
+
+        assert_eq!(selector.select_timeout(std::time::Duration::from_millis(20)), None);
+    }
+}
+
+#[cfg(all(test, not(feature = "blocking")))]
+mod lockfree_tests {
+    use super::*;
+
+    #[test]
+    fn test_construct() {
+        let bb: BoundBuffer<f32> = BoundBuffer::new(30);
+
+        // rounded up to the next power of two
+        assert_eq!(bb.size, 32);
+        assert_eq!(bb.mask, 31);
+        assert_eq!(bb.buffer.len(), 32);
+    }
+
+    #[test]
+    fn test_queue_dequeue() {
+        let bb: BoundBuffer<f32> = BoundBuffer::new(4);
+        bb.queue(1f32);
+        bb.queue(2f32);
+
+        assert_eq!(bb.dequeue(), Some(1f32));
+        assert_eq!(bb.dequeue(), Some(2f32));
+    }
+
+    #[test]
+    fn test_wraps_around_ring() {
+        let bb: BoundBuffer<f32> = BoundBuffer::new(4);
+        for i in 0..16 {
+            bb.queue(i as f32);
+            assert_eq!(bb.dequeue(), Some(i as f32));
+        }
+    }
+
+    #[test]
+    fn test_try_queue_full() {
+        // capacity 1 can't distinguish "just written" from "just freed" (both collapse to the
+        // same sequence number), so exercise fullness with a capacity that can.
+        let bb: BoundBuffer<f32> = BoundBuffer::new(2);
+        assert_eq!(bb.try_queue(1f32), Ok(()));
+        assert_eq!(bb.try_queue(2f32), Ok(()));
+        assert_eq!(bb.try_queue(3f32), Err(3f32));
+    }
+
+    #[test]
+    fn test_try_dequeue_empty() {
+        let bb: BoundBuffer<f32> = BoundBuffer::new(1);
+        assert_eq!(bb.try_dequeue(), None);
+        bb.queue(3f32);
+        assert_eq!(bb.try_dequeue(), Some(3f32));
+        assert_eq!(bb.try_dequeue(), None);
+    }
+
+    #[test]
+    fn test_dequeue_timeout_elapses() {
+        let bb: BoundBuffer<f32> = BoundBuffer::new(1);
+        let start = std::time::Instant::now();
+        assert_eq!(bb.dequeue_timeout(std::time::Duration::from_millis(20)), None);
+        assert!(start.elapsed() >= std::time::Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_queue_timeout_elapses() {
+        let bb: BoundBuffer<f32> = BoundBuffer::new(2);
+        bb.queue(1f32);
+        bb.queue(2f32);
+        assert_eq!(
+            bb.queue_timeout(3f32, std::time::Duration::from_millis(20)),
+            Err(3f32)
+        );
+    }
+
+    #[test]
+    fn test_queue_dequeue_timeout_succeed() {
+        let bb: BoundBuffer<f32> = BoundBuffer::new(1);
+        assert_eq!(bb.queue_timeout(3f32, std::time::Duration::from_millis(20)), Ok(()));
+        assert_eq!(
+            bb.dequeue_timeout(std::time::Duration::from_millis(20)),
+            Some(3f32)
+        );
+    }
+
+    #[test]
+    fn test_selector_try_select_none_ready() {
+        let a: BoundBuffer<f32> = BoundBuffer::new(10);
+        let b: BoundBuffer<f32> = BoundBuffer::new(10);
+        let mut selector = Selector::new();
+        selector.add_dequeue(&a);
+        selector.add_dequeue(&b);
+
+        assert_eq!(selector.try_select(), None);
+    }
+
+    #[test]
+    fn test_selector_select_picks_ready_buffer() {
+        let a: BoundBuffer<f32> = BoundBuffer::new(10);
+        let b: BoundBuffer<f32> = BoundBuffer::new(10);
+        let mut selector = Selector::new();
+        selector.add_dequeue(&a);
+        selector.add_dequeue(&b);
+
+        b.queue(1f32);
+
+        assert_eq!(selector.select(), 1);
+    }
+
+    #[test]
+    fn test_selector_select_timeout_elapses() {
+        let a: BoundBuffer<f32> = BoundBuffer::new(10);
+        let mut selector = Selector::new();
+        selector.add_dequeue(&a);
+
+        assert_eq!(selector.select_timeout(std::time::Duration::from_millis(20)), None);
+    }
+
+    #[test]
+    fn test_concurrent_producers() {
+        let bb: Arc<BoundBuffer<usize>> = Arc::new(BoundBuffer::new(8));
+        let producers: Vec<_> = (0..4)
+            .map(|p| {
+                let bb = bb.clone();
+                std::thread::spawn(move || {
+                    for i in 0..100 {
+                        bb.queue(p * 100 + i);
+                    }
+                })
+            })
+            .collect();
+
+        let mut received = Vec::with_capacity(400);
+        for _ in 0..400 {
+            received.push(bb.dequeue().unwrap());
+        }
+        for producer in producers {
+            producer.join().unwrap();
+        }
+
+        received.sort_unstable();
+        assert_eq!(received, (0..400).collect::<Vec<_>>());
+    }
+}
+
+#[cfg(test)]
+mod histogram_tests {
+    use super::*;
+
+    #[test]
+    fn test_with_buckets_cumulative_counts() {
+        let mut hist = Histogram::with_buckets(vec![1f32, 5f32, 10f32]);
+        hist.fill(0.5);
+        hist.fill(3f32);
+        hist.fill(3f32);
+        hist.fill(8f32);
+        hist.fill(20f32); // overflow, +Inf bucket
+
+        assert_eq!(hist.cumulative_counts(), vec![1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_quantile_interpolates_within_bucket() {
+        let mut hist = Histogram::with_buckets(vec![10f32, 20f32, 30f32]);
+        for _ in 0..10 {
+            hist.fill(5f32);
+        }
+        for _ in 0..10 {
+            hist.fill(15f32);
+        }
+
+        // half the entries fall in the [0, 10] bucket, so the median sits at its upper bound
+        assert_eq!(hist.quantile(0.5), 10f32);
+    }
+
+    #[test]
+    fn test_mean_variance_std_dev() {
+        let mut hist = Histogram::new(10, 0f32, 10f32, 100f32);
+        hist.fill(2f32);
+        hist.fill(4f32);
+        hist.fill(6f32);
+
+        assert_eq!(hist.entries(), 3);
+        assert_eq!(hist.mean(), 4f32);
+        assert!((hist.variance() - 8f32 / 3f32).abs() < 1e-5);
+        assert!((hist.std_dev() - (8f32 / 3f32).sqrt()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_underflow_overflow() {
+        let mut hist = Histogram::new(10, 0f32, 10f32, 100f32);
+        hist.fill(-1f32);
+        hist.fill(11f32);
+        hist.fill(5f32);
+
+        assert_eq!(hist.underflow_overflow(), (1, 1));
+        assert_eq!(hist.entries(), 3);
+    }
+
+    #[test]
+    fn test_mode_returns_center_of_busiest_bin() {
+        let mut hist = Histogram::new(10, 0f32, 10f32, 100f32);
+        hist.fill(5f32);
+        hist.fill(5.5f32);
+        hist.fill(1f32);
+
+        assert_eq!(hist.mode(), 5.5f32);
+    }
+
+    #[test]
+    fn test_merge_combines_compatible_histograms() {
+        let mut a = Histogram::new(10, 0f32, 10f32, 100f32);
+        a.fill(2f32);
+        let mut b = Histogram::new(10, 0f32, 10f32, 100f32);
+        b.fill(2f32);
+        b.fill(8f32);
+
+        a.merge(&b).unwrap();
+
+        assert_eq!(a.entries(), 3);
+        assert_eq!(a.mean(), 4f32);
+    }
+
+    #[test]
+    fn test_merge_rejects_incompatible_bounds() {
+        let mut a = Histogram::new(10, 0f32, 10f32, 100f32);
+        let b = Histogram::new(10, 0f32, 20f32, 100f32);
+
+        assert_eq!(a.merge(&b), Err(MergeError::BoundsMismatch));
+    }
+
+    #[test]
+    fn test_merge_rejects_incompatible_bin_count() {
+        let mut a = Histogram::new(10, 0f32, 10f32, 100f32);
+        let b = Histogram::new(5, 0f32, 10f32, 100f32);
+
+        assert_eq!(
+            a.merge(&b),
+            Err(MergeError::BinCountMismatch {
+                expected: 10,
+                found: 5
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip_preserves_counts() {
+        let mut hist = Histogram::new(10, 0f32, 10f32, 100f32);
+        hist.fill(2f32);
+        hist.fill(4f32);
+        hist.fill(6f32);
+
+        let json = serde_json::to_string(&hist).unwrap();
+        let restored: Histogram = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.entries(), hist.entries());
+        assert_eq!(restored.mean(), hist.mean());
+        assert_eq!(restored.cumulative_counts(), hist.cumulative_counts());
+    }
 }
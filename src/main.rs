@@ -1,6 +1,6 @@
 use bbr::*;
 
-use clap::{command, Parser};
+use clap::Parser;
 use rand::Rng;
 use rand_distr::{Distribution, Normal};
 use std::thread;
@@ -45,13 +45,12 @@ fn sampler(buffer: &BoundBuffer<f32>, samples: usize) {
 /// Uses a [`Histogram`] to plot samples pushed to a [`BoundBuffer`].
 /// Fill function returns the bin the value belongs to
 /// Draw function plots the change of this bin to terminal.
-/// Plots `iterations` number of samples.
+/// Drains the buffer until it is closed and empty, rather than a precomputed sample count.
 /// Artificial delay to simulate slow producer may be added with `delay` in ms.
-fn plotter(buffer: &BoundBuffer<f32>, iterations: usize, delay: u64) {
+fn plotter(buffer: &BoundBuffer<f32>, delay: u64) {
     let mut hist: Histogram = Histogram::new(60, 0f32, 60f32, 1000f32);
     hist.draw_pad();
-    for _ in 0..iterations {
-        let val = buffer.dequeue();
+    while let Some(val) = buffer.dequeue() {
         let bin = hist.fill(val);
         hist.draw(bin);
         thread::sleep(std::time::Duration::from_millis(delay));
@@ -65,7 +64,6 @@ fn main() {
     let producers = args.producers;
     let delay = args.delay;
     let samples = args.samples;
-    let iterations = producers * samples;
 
     print!("{}", termion::clear::All);
 
@@ -82,15 +80,23 @@ fn main() {
         handles.push(thread::spawn(move || sampler(&production_buffer, samples)))
     }
 
+    // once every producer has finished, close the buffer so the visualiser can stop draining
+    // and exit instead of blocking forever.
+    let closer_buffer = bound_buffer.clone();
+    let closer = thread::spawn(move || {
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        closer_buffer.close();
+    });
+
     // create the consumer thread, visualises all generated samples.
     let vis_buff = bound_buffer.clone();
-    let visualiser = thread::spawn(move || plotter(&vis_buff, iterations, delay));
+    let visualiser = thread::spawn(move || plotter(&vis_buff, delay));
 
     // wait for all threads to join.
     visualiser.join().unwrap();
-    for handle in handles {
-        handle.join().unwrap()
-    }
+    closer.join().unwrap();
 
     println!("\nIteration finished");
 }